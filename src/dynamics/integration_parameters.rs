@@ -0,0 +1,58 @@
+use crate::math::Real;
+
+/// Selects how `VelocityGroundConstraintWithManifoldFriction::solve` resolves the
+/// non-penetration impulses of a multi-point manifold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NormalConstraintSolverMode {
+    /// Resolve each contact point one at a time (projected Gauss-Seidel). Biases the
+    /// impulse toward the first point in the manifold.
+    ProjectedGaussSeidel,
+    /// Directly solve pairs of coupled points with a 2x2 block LCP, falling back to
+    /// `ProjectedGaussSeidel` for an odd leftover point or an ill-conditioned pair.
+    TwoPointBlock,
+}
+
+impl Default for NormalConstraintSolverMode {
+    fn default() -> Self {
+        Self::ProjectedGaussSeidel
+    }
+}
+
+/// Parameters tuning the time-stepping scheme and constraint solver.
+#[derive(Copy, Clone, Debug)]
+pub struct IntegrationParameters {
+    pub dt: Real,
+    pub warmstart_coeff: Real,
+    pub restitution_velocity_threshold: Real,
+    pub normal_constraint_solver_mode: NormalConstraintSolverMode,
+    /// Enables split-impulse (pseudo-velocity) position correction: penetration recovery
+    /// is solved against a separate velocity buffer instead of being folded into the real
+    /// `rhs`, so it can't add energy to (or steal energy from) the real velocity solve.
+    pub split_impulse_enabled: bool,
+    /// Caps how fast the split-impulse solver is allowed to push two penetrating bodies
+    /// apart per step.
+    pub max_penetration_correction_speed: Real,
+}
+
+impl Default for IntegrationParameters {
+    fn default() -> Self {
+        Self {
+            dt: 1.0 / 60.0,
+            warmstart_coeff: 1.0,
+            restitution_velocity_threshold: 1.0,
+            normal_constraint_solver_mode: NormalConstraintSolverMode::default(),
+            split_impulse_enabled: false,
+            max_penetration_correction_speed: Real::MAX,
+        }
+    }
+}
+
+impl IntegrationParameters {
+    pub fn inv_dt(&self) -> Real {
+        if self.dt == 0.0 {
+            0.0
+        } else {
+            1.0 / self.dt
+        }
+    }
+}
@@ -0,0 +1,166 @@
+#[cfg(feature = "parallel")]
+use crate::dynamics::solver::{DeltaVel, VelocityGroundConstraintWithManifoldFriction};
+#[cfg(feature = "parallel")]
+use crate::math::Real;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// The islands (i.e. the `mj_lambda`/`mj_lambda2` slots) a single constraint writes to.
+/// Two constraints conflict, and therefore can't be solved in the same batch, whenever
+/// they share an island here.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ConstraintIslands {
+    pub island1: Option<usize>,
+    pub island2: usize,
+}
+
+/// A set of constraint indices that can be solved in parallel: no two constraints in a
+/// batch touch the same island, so `solve`/`warmstart` can run across threads with no
+/// data races on `mj_lambda`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ConstraintBatch {
+    pub constraint_ids: Vec<usize>,
+}
+
+/// Greedily partitions constraints into color classes (batches) so that, within a batch,
+/// no two constraints write to the same island's `mj_lambda` slot: each constraint gets the
+/// lowest-numbered batch not already used by a conflicting neighbor, with `max_batch_size`
+/// bounding batch size so work chunks stay cache-friendly.
+pub(crate) fn color_constraints(
+    islands: &[ConstraintIslands],
+    max_batch_size: usize,
+) -> Vec<ConstraintBatch> {
+    let mut batches: Vec<ConstraintBatch> = Vec::new();
+    // For each island, the colors already claimed by a constraint touching it.
+    let mut island_colors: HashMap<usize, Vec<usize>> = HashMap::new();
+
+    for (constraint_id, touched) in islands.iter().enumerate() {
+        let mut color = 0;
+
+        loop {
+            let conflicts_island2 = island_colors
+                .get(&touched.island2)
+                .map_or(false, |colors| colors.contains(&color));
+            let conflicts_island1 = touched.island1.map_or(false, |island1| {
+                island_colors
+                    .get(&island1)
+                    .map_or(false, |colors| colors.contains(&color))
+            });
+            let batch_full = batches
+                .get(color)
+                .map_or(false, |batch| batch.constraint_ids.len() >= max_batch_size);
+
+            if !conflicts_island2 && !conflicts_island1 && !batch_full {
+                break;
+            }
+
+            color += 1;
+        }
+
+        if color == batches.len() {
+            batches.push(ConstraintBatch::default());
+        }
+
+        batches[color].constraint_ids.push(constraint_id);
+        island_colors
+            .entry(touched.island2)
+            .or_default()
+            .push(color);
+
+        if let Some(island1) = touched.island1 {
+            island_colors.entry(island1).or_default().push(color);
+        }
+    }
+
+    batches
+}
+
+// Carries a raw pointer across the `par_iter` closure boundary. Only `Sync` because
+// `solve_batched` never hands out two indices from the same batch that alias: that's
+// exactly what `color_constraints` guarantees.
+#[cfg(feature = "parallel")]
+struct BatchPtr<T>(*mut T);
+
+#[cfg(feature = "parallel")]
+unsafe impl<T> Sync for BatchPtr<T> {}
+
+/// Warmstarts and solves every constraint one batch at a time: within a batch, no two
+/// constraints touch the same `mj_lambda` island (see `color_constraints`), so the batch's
+/// constraints are warmstarted, then solved, across the rayon thread pool; batches
+/// themselves still run in sequence. `mj_lambdas` needs one slot for every island offset a
+/// constraint's `mj_lambda2` refers to.
+#[cfg(feature = "parallel")]
+pub(crate) fn solve_batched(
+    constraints: &mut [VelocityGroundConstraintWithManifoldFriction],
+    mj_lambdas: &mut [DeltaVel<Real>],
+    max_batch_size: usize,
+) {
+    let islands: Vec<_> = constraints.iter().map(|c| c.islands()).collect();
+    let batches = color_constraints(&islands, max_batch_size);
+
+    let constraints_len = constraints.len();
+    let mj_lambdas_len = mj_lambdas.len();
+    let constraints_ptr = BatchPtr(constraints.as_mut_ptr());
+    let mj_lambdas_ptr = BatchPtr(mj_lambdas.as_mut_ptr());
+
+    for batch in &batches {
+        batch.constraint_ids.par_iter().for_each(|&id| {
+            debug_assert!(id < constraints_len);
+            // SAFETY: `color_constraints` guarantees no two constraints in this batch
+            // share an island, so no other thread running this closure touches either
+            // the `id`-th constraint or the `mj_lambda2` slot it writes to.
+            let constraint = unsafe { &mut *constraints_ptr.0.add(id) };
+            let mj_lambdas =
+                unsafe { std::slice::from_raw_parts_mut(mj_lambdas_ptr.0, mj_lambdas_len) };
+            constraint.warmstart(mj_lambdas);
+        });
+
+        batch.constraint_ids.par_iter().for_each(|&id| {
+            // SAFETY: see above.
+            let constraint = unsafe { &mut *constraints_ptr.0.add(id) };
+            let mj_lambdas =
+                unsafe { std::slice::from_raw_parts_mut(mj_lambdas_ptr.0, mj_lambdas_len) };
+            constraint.solve(mj_lambdas);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn islands(island2: usize) -> ConstraintIslands {
+        ConstraintIslands {
+            island1: None,
+            island2,
+        }
+    }
+
+    #[test]
+    fn disjoint_islands_share_one_batch() {
+        let touched = vec![islands(0), islands(1), islands(2)];
+        let batches = color_constraints(&touched, usize::MAX);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].constraint_ids, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn shared_island_splits_into_separate_batches() {
+        // Constraints 0 and 1 both touch island 0: they must land in different batches.
+        let touched = vec![islands(0), islands(0)];
+        let batches = color_constraints(&touched, usize::MAX);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].constraint_ids, vec![0]);
+        assert_eq!(batches[1].constraint_ids, vec![1]);
+    }
+
+    #[test]
+    fn max_batch_size_forces_a_new_batch() {
+        let touched = vec![islands(0), islands(1), islands(2)];
+        let batches = color_constraints(&touched, 2);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].constraint_ids, vec![0, 1]);
+        assert_eq!(batches[1].constraint_ids, vec![2]);
+    }
+}
@@ -1,6 +1,7 @@
 use super::{DeltaVel, SpringRegularization};
 use crate::dynamics::solver::{AnyVelocityConstraint, VelocityGroundConstraint};
-use crate::dynamics::{IntegrationParameters, RigidBodySet};
+use crate::dynamics::{IntegrationParameters, NormalConstraintSolverMode, RigidBodySet};
+use crate::geometry::internal_edges::{correct_manifold_normal, InternalEdgeAdjacency};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
 use crate::math::{AngVector, Point, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{WAngularInertia, WBasis, WCross, WDot};
@@ -40,6 +41,9 @@ pub(crate) struct VelocityGroundConstraintWithManifoldFriction {
     twist_part: VelocityConstraintElementPart,
     twist_weights: [Real; MAX_MANIFOLD_POINTS],
     impulse_scale: Real,
+    normal_solver_mode: NormalConstraintSolverMode,
+    // Split-impulse (pseudo-velocity) bias parts; all-zero (a no-op) when disabled.
+    bias_parts: [VelocityConstraintElementPart; MAX_MANIFOLD_POINTS],
 }
 
 impl VelocityGroundConstraintWithManifoldFriction {
@@ -56,7 +60,17 @@ impl VelocityGroundConstraintWithManifoldFriction {
         bodies: &RigidBodySet,
         out_constraints: &mut Vec<AnyVelocityConstraint>,
         push: bool,
+        internal_edge: Option<InternalEdgeAdjacency>,
     ) {
+        // A ghost collision against a concave/flat internal edge: generate nothing for it.
+        let normal = match internal_edge {
+            Some(adjacency) => match correct_manifold_normal(manifold.data.normal, adjacency) {
+                Some(normal) => normal,
+                None => return,
+            },
+            None => manifold.data.normal,
+        };
+
         let (erp, cfm, impulse_scale) =
             SpringRegularization::default().erp_cfm_impulse_scale(params.dt);
 
@@ -67,9 +81,9 @@ impl VelocityGroundConstraintWithManifoldFriction {
 
         let force_dir1 = if flipped {
             std::mem::swap(&mut rb1, &mut rb2);
-            manifold.data.normal
+            normal
         } else {
-            -manifold.data.normal
+            -normal
         };
 
         let mj_lambda2 = rb2.island_offset;
@@ -95,10 +109,13 @@ impl VelocityGroundConstraintWithManifoldFriction {
                 twist_part: VelocityConstraintElementPart::zero(),
                 twist_weights: [0.0; MAX_MANIFOLD_POINTS],
                 impulse_scale,
+                normal_solver_mode: params.normal_constraint_solver_mode,
+                bias_parts: [VelocityConstraintElementPart::zero(); MAX_MANIFOLD_POINTS],
             };
 
             let mut manifold_center = Point::origin();
             let mut tangent_impulses = [0.0, 0.0];
+            let mut tangent_velocity = Vector::zeros();
 
             for k in 0..manifold_points.len() {
                 let manifold_point = &manifold_points[k];
@@ -106,6 +123,8 @@ impl VelocityGroundConstraintWithManifoldFriction {
                 let dp2 = manifold_point.point - rb2.world_com;
 
                 manifold_center += manifold_point.point.coords / (manifold_points.len() as Real);
+                tangent_velocity +=
+                    manifold_point.tangent_velocity / (manifold_points.len() as Real);
 
                 let vel1 = rb1.linvel + rb1.angvel.gcross(dp1);
                 let vel2 = rb2.linvel + rb2.angvel.gcross(dp2);
@@ -125,8 +144,21 @@ impl VelocityGroundConstraintWithManifoldFriction {
                     rhs += manifold_point.restitution * rhs
                 }
 
+                // With split-impulse on, the Baumgarte term goes to `bias_rhs` (solved
+                // separately below) instead of the real `rhs`, so it never contaminates
+                // restitution or momentum.
+                let mut bias_rhs = 0.0;
+
                 if manifold_point.dist < 0.0 {
-                    rhs += manifold_point.dist * erp;
+                    if params.split_impulse_enabled {
+                        bias_rhs = bias_rhs_for_penetration(
+                            manifold_point.dist,
+                            erp,
+                            params.max_penetration_correction_speed,
+                        );
+                    } else {
+                        rhs += manifold_point.dist * erp;
+                    }
                 } else {
                     rhs += manifold_point.dist * inv_dt;
                 }
@@ -141,6 +173,12 @@ impl VelocityGroundConstraintWithManifoldFriction {
                     impulse,
                     r,
                 };
+                constraint.bias_parts[k] = VelocityConstraintElementPart {
+                    gcross2,
+                    rhs: bias_rhs,
+                    impulse: manifold_point.data.pseudo_impulse,
+                    r,
+                };
             }
 
             // Tangent part.
@@ -157,7 +195,11 @@ impl VelocityGroundConstraintWithManifoldFriction {
                     .effective_world_inv_inertia_sqrt
                     .transform_vector(dp2.gcross(-tangents1[j]));
                 let r = 1.0 / (rb2.effective_inv_mass + gcross2.gdot(gcross2));
-                let rhs = (vel1 - vel2).dot(&tangents1[j]);
+                // Drive toward the belt's surface velocity instead of toward zero.
+                let rhs = conveyor_tangent_rhs(
+                    (vel1 - vel2).dot(&tangents1[j]),
+                    tangent_velocity.dot(&tangents1[j]),
+                );
                 let impulse = tangent_impulses[j] * warmstart_coeff;
 
                 constraint.tangent_parts[j] = VelocityConstraintElementPart {
@@ -195,6 +237,16 @@ impl VelocityGroundConstraintWithManifoldFriction {
         }
     }
 
+    // The island slot(s) this constraint writes to. Ground constraints only ever touch
+    // the dynamic body's island, so `island1` is always `None` here; used by the
+    // graph-coloring batching pass to detect conflicts between constraints.
+    pub(crate) fn islands(&self) -> super::constraint_batch::ConstraintIslands {
+        super::constraint_batch::ConstraintIslands {
+            island1: None,
+            island2: self.mj_lambda2,
+        }
+    }
+
     pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
         let mut mj_lambda2 = DeltaVel::zero();
 
@@ -226,6 +278,111 @@ impl VelocityGroundConstraintWithManifoldFriction {
         mj_lambdas[self.mj_lambda2 as usize].angular += mj_lambda2.angular;
     }
 
+    // Warmstarts the split-impulse bias; a no-op when split-impulse is disabled.
+    pub fn warmstart_bias(&self, pseudo_mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda2 = DeltaVel::zero();
+
+        for i in 0..self.num_contacts as usize {
+            let elt = &self.bias_parts[i];
+            mj_lambda2.linear += self.dir1 * (-self.im2 * elt.impulse);
+            mj_lambda2.angular += elt.gcross2 * elt.impulse;
+        }
+
+        pseudo_mj_lambdas[self.mj_lambda2 as usize].linear += mj_lambda2.linear;
+        pseudo_mj_lambdas[self.mj_lambda2 as usize].angular += mj_lambda2.angular;
+    }
+
+    // Solves the split-impulse constraint against the pseudo-velocity buffer only; never
+    // touches the real `mj_lambdas`.
+    pub fn solve_bias(&mut self, pseudo_mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda2 = pseudo_mj_lambdas[self.mj_lambda2 as usize];
+
+        for i in 0..self.num_contacts as usize {
+            let elt = &mut self.bias_parts[i];
+            let dimpulse =
+                -self.dir1.dot(&mj_lambda2.linear) + elt.gcross2.gdot(mj_lambda2.angular) + elt.rhs;
+            let new_impulse = (elt.impulse - elt.r * dimpulse).max(0.0);
+            let dlambda = new_impulse - elt.impulse;
+            elt.impulse = new_impulse;
+
+            mj_lambda2.linear += self.dir1 * (-self.im2 * dlambda);
+            mj_lambda2.angular += elt.gcross2 * dlambda;
+        }
+
+        pseudo_mj_lambdas[self.mj_lambda2 as usize] = mj_lambda2;
+    }
+
+    // Single-point projected Gauss-Seidel update for the non-penetration constraint `i`.
+    fn solve_normal_point(&mut self, i: usize, mj_lambda2: &mut DeltaVel<Real>) {
+        let elt = &mut self.normal_parts[i];
+        let dimpulse =
+            -self.dir1.dot(&mj_lambda2.linear) + elt.gcross2.gdot(mj_lambda2.angular) + elt.rhs;
+        let new_impulse = (elt.impulse * self.impulse_scale - elt.r * dimpulse).max(0.0);
+        let dlambda = new_impulse - elt.impulse;
+        elt.impulse = new_impulse;
+
+        mj_lambda2.linear += self.dir1 * (-self.im2 * dlambda);
+        mj_lambda2.angular += elt.gcross2 * dlambda;
+    }
+
+    // Box2D-style 2x2 block LCP solve for the coupled points `i` and `j`.
+    fn solve_normal_block(&mut self, i: usize, j: usize, mj_lambda2: &mut DeltaVel<Real>) {
+        let k11 = self.im2
+            + self.normal_parts[i]
+                .gcross2
+                .gdot(self.normal_parts[i].gcross2);
+        let k22 = self.im2
+            + self.normal_parts[j]
+                .gcross2
+                .gdot(self.normal_parts[j].gcross2);
+        let k12 = self.im2
+            + self.normal_parts[i]
+                .gcross2
+                .gdot(self.normal_parts[j].gcross2);
+        let det = k11 * k22 - k12 * k12;
+
+        // Condition-number check (as in Box2D), not just an exact-singularity check.
+        const MAX_CONDITION_NUMBER: Real = 1000.0;
+
+        if det <= 0.0 || k11 * k11 >= MAX_CONDITION_NUMBER * det {
+            // Degenerate/ill-conditioned coupling: fall back to PGS for both points.
+            self.solve_normal_point(i, mj_lambda2);
+            self.solve_normal_point(j, mj_lambda2);
+            return;
+        }
+
+        let det_inv = 1.0 / det;
+        // `raw_a1`/`raw_a2` are the un-regularized previous impulses `mj_lambda2` already
+        // reflects (via `warmstart`/earlier iterations); `a1`/`a2` are only the
+        // regularized copies used to build the residual, matching `solve_normal_point`.
+        let raw_a1 = self.normal_parts[i].impulse;
+        let raw_a2 = self.normal_parts[j].impulse;
+        let a1 = raw_a1 * self.impulse_scale;
+        let a2 = raw_a2 * self.impulse_scale;
+
+        let d1 = -self.dir1.dot(&mj_lambda2.linear)
+            + self.normal_parts[i].gcross2.gdot(mj_lambda2.angular)
+            + self.normal_parts[i].rhs;
+        let d2 = -self.dir1.dot(&mj_lambda2.linear)
+            + self.normal_parts[j].gcross2.gdot(mj_lambda2.angular)
+            + self.normal_parts[j].rhs;
+
+        // Residual with the current impulses' contribution removed.
+        let b1 = d1 - (k11 * a1 + k12 * a2);
+        let b2 = d2 - (k12 * a1 + k22 * a2);
+
+        let (new_a1, new_a2) = solve_two_point_normal_lcp(k11, k22, k12, det_inv, b1, b2);
+
+        let dlambda1 = new_a1 - raw_a1;
+        let dlambda2 = new_a2 - raw_a2;
+        self.normal_parts[i].impulse = new_a1;
+        self.normal_parts[j].impulse = new_a2;
+
+        mj_lambda2.linear += self.dir1 * (-self.im2 * (dlambda1 + dlambda2));
+        mj_lambda2.angular +=
+            self.normal_parts[i].gcross2 * dlambda1 + self.normal_parts[j].gcross2 * dlambda2;
+    }
+
     pub fn solve(&mut self, mj_lambdas: &mut [DeltaVel<Real>]) {
         let mut mj_lambda2 = mj_lambdas[self.mj_lambda2 as usize];
 
@@ -252,16 +409,27 @@ impl VelocityGroundConstraintWithManifoldFriction {
         }
 
         // Solve non-penetration.
-        for i in 0..self.num_contacts as usize {
-            let elt = &mut self.normal_parts[i];
-            let dimpulse =
-                -self.dir1.dot(&mj_lambda2.linear) + elt.gcross2.gdot(mj_lambda2.angular) + elt.rhs;
-            let new_impulse = (elt.impulse * self.impulse_scale - elt.r * dimpulse).max(0.0);
-            let dlambda = new_impulse - elt.impulse;
-            elt.impulse = new_impulse;
+        match self.normal_solver_mode {
+            NormalConstraintSolverMode::ProjectedGaussSeidel => {
+                for i in 0..self.num_contacts as usize {
+                    self.solve_normal_point(i, &mut mj_lambda2);
+                }
+            }
+            NormalConstraintSolverMode::TwoPointBlock => {
+                let num_contacts = self.num_contacts as usize;
+                let mut i = 0;
 
-            mj_lambda2.linear += self.dir1 * (-self.im2 * dlambda);
-            mj_lambda2.angular += elt.gcross2 * dlambda;
+                while i + 1 < num_contacts {
+                    self.solve_normal_block(i, i + 1, &mut mj_lambda2);
+                    i += 2;
+                }
+
+                // Odd point left over (3-point manifolds, or a fallback for
+                // manifolds we didn't pair up): solve it with plain PGS.
+                if i < num_contacts {
+                    self.solve_normal_point(i, &mut mj_lambda2);
+                }
+            }
         }
 
         // Solve twist.
@@ -302,8 +470,162 @@ impl VelocityGroundConstraintWithManifoldFriction {
                 self.tangent_parts[0].impulse * normal_factor,
                 self.tangent_parts[1].impulse * normal_factor,
             ];
+            active_contacts[k_base + k].data.pseudo_impulse = self.bias_parts[k].impulse;
         }
 
         manifold.data.twist_impulse = self.twist_part.impulse;
     }
-}
\ No newline at end of file
+}
+
+// The split-impulse Baumgarte bias for a penetrating point, clamped so the positional
+// correction never pushes the bodies apart faster than `max_correction_speed`.
+fn bias_rhs_for_penetration(dist: Real, erp: Real, max_correction_speed: Real) -> Real {
+    (dist * erp).max(-max_correction_speed)
+}
+
+// The friction target velocity along one tangent direction: the usual relative velocity,
+// offset by the belt's surface velocity along that same direction, so the friction solve
+// drives the contact to match the belt instead of to a standstill.
+fn conveyor_tangent_rhs(relative_velocity: Real, tangent_velocity: Real) -> Real {
+    relative_velocity - tangent_velocity
+}
+
+// Solves the 2x2 normal-impulse LCP for a coupled point pair: `k11`/`k22`/`k12` are the
+// symmetric effective-mass matrix entries, `det_inv` is `1 / (k11*k22 - k12*k12)`, and
+// `b1`/`b2` are the residual velocities with the current impulses' contribution removed.
+// Returns the new (non-negative) impulse pair, enumerating the four LCP cases in turn.
+fn solve_two_point_normal_lcp(
+    k11: Real,
+    k22: Real,
+    k12: Real,
+    det_inv: Real,
+    b1: Real,
+    b2: Real,
+) -> (Real, Real) {
+    // Case 1: both points active (x = -K^-1 * b).
+    let x1 = det_inv * (-k22 * b1 + k12 * b2);
+    let x2 = det_inv * (k12 * b1 - k11 * b2);
+
+    if x1 >= 0.0 && x2 >= 0.0 {
+        return (x1, x2);
+    }
+
+    // Case 2: only point `i` active.
+    let x1 = -b1 / k11;
+    let vn2 = b2 + k12 * x1;
+
+    if x1 >= 0.0 && vn2 >= 0.0 {
+        return (x1, 0.0);
+    }
+
+    // Case 3: only point `j` active.
+    let x2 = -b2 / k22;
+    let vn1 = b1 + k12 * x2;
+
+    if x2 >= 0.0 && vn1 >= 0.0 {
+        return (0.0, x2);
+    }
+
+    // Case 4: neither point active (both separating).
+    (0.0, 0.0)
+}
+
+// Integrates a pseudo-velocity into the position correction applied for one step: small
+// enough that treating it as linear (`pseudo_linvel * dt`, `pseudo_angvel * dt`) rather than
+// composing an exact rotation is indistinguishable from the real thing.
+fn pseudo_position_correction(
+    pseudo_linvel: Vector<Real>,
+    pseudo_angvel: AngVector<Real>,
+    dt: Real,
+) -> (Vector<Real>, AngVector<Real>) {
+    (pseudo_linvel * dt, pseudo_angvel * dt)
+}
+
+/// Runs the split-impulse solver for one step and returns, per island slot, the
+/// translation/rotation correction to add directly to the body's position (never to
+/// `linvel`/`angvel`) so that penetration recovery doesn't add energy to the real velocity
+/// solve. A no-op (all corrections zero) when every constraint has split-impulse disabled,
+/// since their `bias_parts` are then seeded at zero and never perturbed.
+pub(crate) fn solve_split_impulse_positions(
+    constraints: &mut [VelocityGroundConstraintWithManifoldFriction],
+    num_islands: usize,
+    num_solver_iterations: usize,
+    dt: Real,
+) -> Vec<(Vector<Real>, AngVector<Real>)> {
+    let mut pseudo_mj_lambdas = vec![DeltaVel::zero(); num_islands];
+
+    for constraint in constraints.iter() {
+        constraint.warmstart_bias(&mut pseudo_mj_lambdas);
+    }
+
+    for _ in 0..num_solver_iterations {
+        for constraint in constraints.iter_mut() {
+            constraint.solve_bias(&mut pseudo_mj_lambdas);
+        }
+    }
+
+    pseudo_mj_lambdas
+        .into_iter()
+        .map(|pseudo| pseudo_position_correction(pseudo.linear, pseudo.angular, dt))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conveyor_rhs_matches_belt_at_belt_speed() {
+        // Body already moving at the belt's speed: friction target is zero.
+        assert_eq!(conveyor_tangent_rhs(2.0, 2.0), 0.0);
+    }
+
+    #[test]
+    fn conveyor_rhs_nonzero_without_belt_speed() {
+        assert_eq!(conveyor_tangent_rhs(2.0, 0.0), 2.0);
+    }
+
+    #[test]
+    fn two_point_lcp_both_active() {
+        // Symmetric, well-conditioned K; both points end up with a positive impulse.
+        let (x1, x2) = solve_two_point_normal_lcp(2.0, 2.0, 1.0, 1.0 / 3.0, -4.0, -4.0);
+        assert!((x1 - x2).abs() < 1.0e-6);
+        assert!(x1 > 0.0 && x2 > 0.0);
+    }
+
+    #[test]
+    fn two_point_lcp_only_first_active() {
+        // Point `i` is approaching fast, point `j` only slowly: case 2 should win, with
+        // `j` left at zero impulse and still separating (residual >= 0).
+        let (x1, x2) = solve_two_point_normal_lcp(2.0, 2.0, 1.0, 1.0 / 3.0, -5.0, -1.0);
+        assert!((x1 - 2.5).abs() < 1.0e-6);
+        assert_eq!(x2, 0.0);
+    }
+
+    #[test]
+    fn two_point_lcp_neither_active() {
+        // Both points separating: no impulse needed.
+        let (x1, x2) = solve_two_point_normal_lcp(2.0, 2.0, 1.0, 1.0 / 3.0, 1.0, 1.0);
+        assert_eq!((x1, x2), (0.0, 0.0));
+    }
+
+    #[test]
+    fn bias_rhs_unclamped_for_shallow_penetration() {
+        assert!((bias_rhs_for_penetration(-0.1, 0.2, 10.0) - (-0.02)).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn bias_rhs_clamped_to_max_correction_speed() {
+        assert_eq!(bias_rhs_for_penetration(-10.0, 0.2, 1.0), -1.0);
+    }
+
+    #[test]
+    fn pseudo_position_correction_scales_by_dt() {
+        let pseudo_linvel = Vector::<Real>::repeat(2.0);
+        let pseudo_angvel = AngVector::<Real>::repeat(3.0);
+        assert_eq!(
+            pseudo_position_correction(pseudo_linvel, pseudo_angvel, 0.5),
+            (pseudo_linvel * 0.5, pseudo_angvel * 0.5)
+        );
+    }
+}
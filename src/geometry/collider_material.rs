@@ -0,0 +1,28 @@
+use crate::math::{Real, Vector};
+
+/// Contact material properties of a collider. Narrow-phase copies these onto every
+/// `SolverContact` it generates for that collider.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColliderMaterial {
+    pub friction: Real,
+    pub restitution: Real,
+    /// Tangential surface velocity, e.g. to make this collider act as a conveyor belt:
+    /// the friction solver drives relative tangential velocity at the contact toward
+    /// this value instead of toward zero, still capped by `friction * normal_impulse`.
+    pub tangent_velocity: Vector<Real>,
+    /// Opt-in to internal-edge correction for a triangle-mesh/heightfield collider: narrow-phase
+    /// then attaches edge adjacency to each manifold generated against it, so the solver can
+    /// drop or clamp ghost contacts from `internal_edges::correct_manifold_normal`.
+    pub fix_internal_edges: bool,
+}
+
+impl Default for ColliderMaterial {
+    fn default() -> Self {
+        Self {
+            friction: 0.5,
+            restitution: 0.0,
+            tangent_velocity: Vector::zeros(),
+            fix_internal_edges: false,
+        }
+    }
+}
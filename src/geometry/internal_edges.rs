@@ -0,0 +1,167 @@
+use crate::math::{Real, Vector};
+
+/// Classification of a contact edge relative to its neighboring face, used to decide
+/// whether a contact generated against that edge is a real feature or a "ghost"
+/// collision against an internal edge of a tessellated triangle mesh / heightfield.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum EdgeClassification {
+    /// The adjacent face bulges away from the contact normal: a real corner.
+    Convex,
+    /// The adjacent face is coplanar (or nearly so): not a real feature.
+    Flat,
+    /// The adjacent face bulges toward the contact normal: an internal edge that should
+    /// never generate a contact on its own.
+    Concave,
+}
+
+const FLAT_EDGE_EPSILON: Real = 1.0e-3;
+
+/// Classifies a triangle/segment edge against its adjacent face, given the owning face's
+/// normal, the adjacent face's normal, and the (shared) edge direction.
+///
+/// Mirrors the edge-adjacency classification used for edge/polygon collision: the sign of
+/// `adjacent_normal`'s component along the hinge axis (`face_normal x edge_dir`) tells us
+/// whether the adjacent face folds away from (`Convex`), along (`Flat`), or into
+/// (`Concave`) the owning face.
+pub(crate) fn classify_edge(
+    face_normal: Vector<Real>,
+    adjacent_normal: Vector<Real>,
+    edge_dir: Vector<Real>,
+) -> EdgeClassification {
+    let hinge = face_normal.cross(&edge_dir);
+    let sin_angle = hinge.dot(&adjacent_normal);
+
+    if sin_angle > FLAT_EDGE_EPSILON {
+        EdgeClassification::Convex
+    } else if sin_angle < -FLAT_EDGE_EPSILON {
+        EdgeClassification::Concave
+    } else {
+        EdgeClassification::Flat
+    }
+}
+
+/// Clamps a contact `normal` generated against a triangle/segment edge into the dihedral
+/// wedge spanned by the two adjacent faces, rotating it onto the nearest wedge boundary
+/// when it strays outside. This eliminates the characteristic "catching" bump produced
+/// when a body slides over a shared edge and narrow-phase briefly reports a normal
+/// pointing along the edge itself instead of along either face.
+///
+/// Measures angles in the plane perpendicular to `edge_dir`, using the same hinge axis
+/// (`face_normal x edge_dir`) `classify_edge` uses: this bounds the wedge by the actual
+/// dihedral angle between the faces, rather than merely testing which hemisphere `normal`
+/// falls in, so a near-flat pair of faces only admits normals in the matching narrow wedge.
+pub(crate) fn clamp_normal_to_cone(
+    normal: Vector<Real>,
+    face_normal: Vector<Real>,
+    adjacent_normal: Vector<Real>,
+    edge_dir: Vector<Real>,
+) -> Vector<Real> {
+    let hinge = face_normal.cross(&edge_dir).normalize();
+    let angle_of = |v: &Vector<Real>| hinge.dot(v).atan2(face_normal.dot(v));
+
+    let angle_adjacent = angle_of(&adjacent_normal);
+    let angle_normal = angle_of(&normal);
+
+    if (0.0..=angle_adjacent).contains(&angle_normal) {
+        return normal;
+    }
+
+    // The nearer boundary around the circle is the one with the larger cosine of the gap.
+    let clamped_angle = if (angle_normal - angle_adjacent).cos() > angle_normal.cos() {
+        angle_adjacent
+    } else {
+        0.0
+    };
+
+    (face_normal * clamped_angle.cos() + hinge * clamped_angle.sin()).normalize()
+}
+
+/// The edge adjacency a manifold was generated against, carried alongside the manifold by
+/// narrow-phase when the touching mesh/heightfield collider opted into internal-edge
+/// correction (see `ColliderMaterial::fix_internal_edges`).
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct InternalEdgeAdjacency {
+    pub face_normal: Vector<Real>,
+    pub adjacent_normal: Vector<Real>,
+    pub edge_dir: Vector<Real>,
+}
+
+/// Called by `VelocityGroundConstraintWithManifoldFriction::generate` before it builds any
+/// constraint for a manifold carrying edge adjacency: `None` means the manifold is a ghost
+/// collision against a concave/flat internal edge and must be dropped, `Some` carries the
+/// normal to solve against (clamped into the adjacent faces' cone for a convex edge).
+pub(crate) fn correct_manifold_normal(
+    normal: Vector<Real>,
+    adjacency: InternalEdgeAdjacency,
+) -> Option<Vector<Real>> {
+    match classify_edge(
+        adjacency.face_normal,
+        adjacency.adjacent_normal,
+        adjacency.edge_dir,
+    ) {
+        EdgeClassification::Concave | EdgeClassification::Flat => None,
+        EdgeClassification::Convex => Some(clamp_normal_to_cone(
+            normal,
+            adjacency.face_normal,
+            adjacency.adjacent_normal,
+            adjacency.edge_dir,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convex_edge_clamps_into_cone() {
+        let face_normal = Vector::new(0.0, 1.0, 0.0);
+        let adjacent_normal = Vector::new(1.0, 0.0, 0.0);
+        let edge_dir = Vector::new(0.0, 0.0, 1.0);
+        let adjacency = InternalEdgeAdjacency {
+            face_normal,
+            adjacent_normal,
+            edge_dir,
+        };
+        // Outside the cone spanned by the two face normals.
+        let normal = Vector::new(-1.0, 0.0, 0.0);
+
+        let corrected = correct_manifold_normal(normal, adjacency).expect("convex edge kept");
+        assert!(corrected.dot(&face_normal) >= 0.0 && corrected.dot(&adjacent_normal) >= 0.0);
+    }
+
+    #[test]
+    fn concave_edge_is_dropped() {
+        let face_normal = Vector::new(0.0, 1.0, 0.0);
+        let adjacent_normal = Vector::new(-1.0, 0.0, 0.0);
+        let edge_dir = Vector::new(0.0, 0.0, 1.0);
+        let adjacency = InternalEdgeAdjacency {
+            face_normal,
+            adjacent_normal,
+            edge_dir,
+        };
+
+        assert!(correct_manifold_normal(face_normal, adjacency).is_none());
+    }
+
+    #[test]
+    fn near_coplanar_floor_rejects_a_mostly_horizontal_ghost_normal() {
+        // Two tessellated floor triangles, almost flush: both face normals are nearly
+        // straight up, tilted by under a degree around the shared edge.
+        let face_normal = Vector::new(0.0, 1.0, 0.0);
+        let tilt = 0.005_f32;
+        let adjacent_normal = Vector::new(tilt, (1.0 - tilt * tilt).sqrt(), 0.0);
+        let edge_dir = Vector::new(0.0, 0.0, 1.0);
+
+        // A ghost normal pointing mostly along the direction of travel (the classic
+        // "catching" bump): its vertical component is non-negative, so the old
+        // hemisphere-only test would have waved it through unclamped.
+        let ghost_normal = Vector::new(0.999, 0.045, 0.0);
+
+        let corrected = clamp_normal_to_cone(ghost_normal, face_normal, adjacent_normal, edge_dir);
+
+        // Clamped onto the narrow wedge between the two near-vertical face normals: far
+        // closer to "up" than the ghost normal ever was.
+        assert!(corrected.dot(&Vector::new(0.0, 1.0, 0.0)) > 0.99);
+    }
+}
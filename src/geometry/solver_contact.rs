@@ -0,0 +1,27 @@
+use crate::math::{Point, Real, Vector};
+
+/// Per-contact data that persists across steps for warmstarting: accumulated impulses
+/// from the previous solve, copied into a `SolverContact` at the start of each step and
+/// written back (via `writeback_impulses`) once the new step has solved.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ManifoldPointData {
+    pub impulse: Real,
+    pub tangent_impulse: [Real; 2],
+    /// Warmstart impulse for the split-impulse (pseudo-velocity) non-penetration
+    /// constraint. See `IntegrationParameters::split_impulse_enabled`.
+    pub pseudo_impulse: Real,
+}
+
+/// A single point of a manifold, prepared by narrow-phase for the velocity solver.
+#[derive(Copy, Clone, Debug)]
+pub struct SolverContact {
+    pub point: Point<Real>,
+    pub dist: Real,
+    pub friction: Real,
+    pub restitution: Real,
+    /// Tangential surface velocity at this contact, e.g. for conveyor belts. Copied from
+    /// the touching collider's `ColliderMaterial::tangent_velocity`; zero for ordinary
+    /// contacts.
+    pub tangent_velocity: Vector<Real>,
+    pub data: ManifoldPointData,
+}